@@ -1,5 +1,5 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Vec};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, Address, Env, Vec};
 
 // Data structures
 #[contracttype]
@@ -10,12 +10,26 @@ pub struct Subscription {
     pub subscriber: Address,
     pub amount: i128,
     pub period_days: u32,
-    pub recipient: Address,
-    pub split_percentage: u32, // Basis points (0-10000, where 10000 = 100%)
+    pub splits: Vec<Split>,
+    pub token: Address,
+    pub escrow: bool,
+    pub escrow_refund_after_seconds: u64,
     pub next_billing_date: u64,
     pub last_payment_date: u64,
     pub is_active: bool,
     pub created_at: u64,
+    pub last_extended_ledger: u32,
+    pub vesting_enabled: bool,
+    pub vesting_duration_seconds: u64,
+    pub vesting_cliff_seconds: u64,
+}
+
+/// One recipient's cut of a renewal payment, in basis points (0-10000, where 10000 = 100%)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Split {
+    pub recipient: Address,
+    pub basis_points: u32,
 }
 
 #[contracttype]
@@ -25,46 +39,109 @@ pub struct RecipientBalance {
     pub balance: i128,
 }
 
+/// A single recipient credit that unlocks linearly between `start_ledger_time`
+/// and `start_ledger_time + duration_seconds`, releasing nothing before the cliff
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingSchedule {
+    pub start_ledger_time: u64,
+    pub duration_seconds: u64,
+    pub total: i128,
+    pub withdrawn: i128,
+    pub cliff_seconds: u64,
+}
+
+/// Escrow and vesting knobs for a new subscription, grouped into one struct so
+/// `create_subscription` doesn't keep growing positional arguments as new
+/// revenue-release modes are added.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubscriptionOptions {
+    pub escrow: bool,
+    pub escrow_refund_after_seconds: u64,
+    pub vesting_enabled: bool,
+    pub vesting_duration_seconds: u64,
+    pub vesting_cliff_seconds: u64,
+}
+
+/// Funds collected for an escrowed subscription's current billing cycle, pending
+/// either `release_escrow` by the owner or `refund_escrow` past `refund_deadline`.
+/// The deadline is re-armed on every renewal so it always reflects the most
+/// recently collected cycle, not just the subscription's first payment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Escrow {
+    pub held: i128,
+    pub refund_deadline: u64,
+}
+
 // Storage keys
 #[contracttype]
 pub enum DataKey {
     Subscription(u64),
     OwnerSubscriptions(Address),
     RecipientBalance(Address),
+    VestingSchedules(Address),
+    Escrow(u64),
     SubscriptionCounter,
+    Token,
+    RentLedgers,
     Initialized,
 }
 
-const MAX_SPLIT_PERCENTAGE: u32 = 10000; // 100% in basis points
+const MAX_SPLIT_BASIS_POINTS: u32 = 10000; // 100% in basis points
+
+// ~5s per ledger close
+const DAY_IN_LEDGERS: u32 = 17280;
+const DEFAULT_RENT_LEDGERS: u32 = 30 * DAY_IN_LEDGERS;
+const BALANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const BALANCE_LIFETIME_THRESHOLD: u32 = BALANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
 
 #[contract]
 pub struct SubSor;
 
 #[contractimpl]
 impl SubSor {
-    /// Initialize the contract (one-time setup)
-    pub fn initialize(env: Env) {
+    /// Initialize the contract (one-time setup) with the payment token and
+    /// the number of ledgers a subscription's storage is extended by on each touch
+    pub fn initialize(env: Env, token: Address, rent_ledgers: u32) {
         // Check if already initialized
         if env.storage().instance().has(&DataKey::Initialized) {
             return;
         }
-        // Initialize counter and mark as initialized
+        // Initialize counter, default token, rent config, and mark as initialized
         env.storage().instance().set(&DataKey::SubscriptionCounter, &0u64);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::RentLedgers, &rent_ledgers);
         env.storage().instance().set(&DataKey::Initialized, &true);
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
     }
 
-    /// Create a new subscription
+    /// Create a new subscription that splits each renewal across `splits`, with any
+    /// remainder going to `owner`. `options.vesting_enabled` unlocks each recipient's
+    /// cut linearly over `options.vesting_duration_seconds` instead of landing in
+    /// their withdrawable balance immediately. `options.escrow` holds renewal
+    /// payments rather than distributing them until `release_escrow` is called.
     pub fn create_subscription(
         env: Env,
         owner: Address,
         subscriber: Address,
         amount: i128,
         period_days: u32,
-        recipient: Address,
-        split_percentage: u32,
+        splits: Vec<Split>,
+        options: SubscriptionOptions,
     ) -> u64 {
         owner.require_auth();
-        
+        // This only authorizes *this* invocation; Soroban auth does not carry over to
+        // the later, separate `renew_subscription` transaction. A token allowance
+        // (set up below) is what actually lets renewals happen without the
+        // subscriber signing again.
+        subscriber.require_auth();
+
         // Validate inputs
         if amount <= 0 {
             panic!("Amount must be positive");
@@ -72,10 +149,39 @@ impl SubSor {
         if period_days == 0 {
             panic!("Period must be at least 1 day");
         }
-        if split_percentage > MAX_SPLIT_PERCENTAGE {
-            panic!("Split percentage cannot exceed 100%");
+        if splits.is_empty() {
+            panic!("At least one split is required");
+        }
+        let mut total_basis_points: u32 = 0;
+        for split in splits.iter() {
+            total_basis_points = total_basis_points.checked_add(split.basis_points).unwrap();
+        }
+        if total_basis_points > MAX_SPLIT_BASIS_POINTS {
+            panic!("Splits cannot exceed 100%");
+        }
+        if options.vesting_enabled && options.vesting_duration_seconds == 0 {
+            panic!("Vesting duration must be positive");
         }
 
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .unwrap_or_else(|| panic!("Contract not initialized"));
+
+        // Grant the contract an unbounded, non-expiring allowance so a third-party
+        // keeper can pull each renewal via `transfer_from` without the subscriber
+        // signing again. Sizing the allowance to a fixed number of periods would dry
+        // up after that many renewals with no re-approval path; actual spend cadence
+        // is already gated by the subscription's own `next_billing_date` check in
+        // `renew_subscription`, so the allowance itself doesn't need to bound it.
+        token::Client::new(&env, &token).approve(
+            &subscriber,
+            &env.current_contract_address(),
+            &i128::MAX,
+            &u32::MAX,
+        );
+
         // Increment subscription counter
         let mut counter: u64 = env
             .storage()
@@ -88,7 +194,7 @@ impl SubSor {
         // Calculate next billing date (current ledger timestamp + period in seconds)
         let current_time = env.ledger().timestamp();
         let period_seconds = (period_days as u64).checked_mul(86400).unwrap();
-        let next_billing = (current_time as u64).checked_add(period_seconds).unwrap();
+        let next_billing = current_time.checked_add(period_seconds).unwrap();
 
         let subscription = Subscription {
             id: counter,
@@ -96,92 +202,105 @@ impl SubSor {
             subscriber: subscriber.clone(),
             amount,
             period_days,
-            recipient: recipient.clone(),
-            split_percentage,
-            next_billing_date: next_billing as u64,
+            splits: splits.clone(),
+            token,
+            escrow: options.escrow,
+            escrow_refund_after_seconds: options.escrow_refund_after_seconds,
+            next_billing_date: next_billing,
             last_payment_date: 0,
             is_active: true,
-            created_at: current_time as u64,
+            created_at: current_time,
+            last_extended_ledger: env.ledger().sequence(),
+            vesting_enabled: options.vesting_enabled,
+            vesting_duration_seconds: options.vesting_duration_seconds,
+            vesting_cliff_seconds: options.vesting_cliff_seconds,
         };
 
         // Store subscription
-        env.storage().instance().set(&DataKey::Subscription(counter), &subscription);
+        Self::save_subscription(&env, &subscription);
 
         // Add to owner's subscription list
-        let mut owner_subs: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&DataKey::OwnerSubscriptions(owner.clone()))
-            .unwrap_or(Vec::new(&env));
+        let mut owner_subs = Self::load_owner_subscriptions(&env, &owner);
         owner_subs.push_back(counter);
-        env.storage().instance().set(&DataKey::OwnerSubscriptions(owner), &owner_subs);
+        Self::save_owner_subscriptions(&env, &owner, &owner_subs);
 
-        // Initialize recipient balance if needed
-        if !env.storage().instance().has(&DataKey::RecipientBalance(recipient.clone())) {
-            env.storage().instance().set(&DataKey::RecipientBalance(recipient), &0i128);
+        // Initialize each recipient's balance if needed
+        for split in splits.iter() {
+            if !env.storage().persistent().has(&DataKey::RecipientBalance(split.recipient.clone())) {
+                Self::save_recipient_balance(&env, &split.recipient, 0);
+            }
         }
 
+        env.events().publish(
+            (symbol_short!("sub"), symbol_short!("created"), counter),
+            (owner, subscriber, splits, amount),
+        );
+
         counter
     }
 
     /// Cancel an active subscription
     pub fn cancel_subscription(env: Env, subscription_id: u64) {
-        let subscription: Subscription = env
-            .storage()
-            .instance()
-            .get(&DataKey::Subscription(subscription_id))
-            .unwrap_or_else(|| panic!("Subscription not found"));
-        
+        let mut subscription = Self::load_subscription(&env, subscription_id);
+
         subscription.owner.require_auth();
-        
+
         if !subscription.is_active {
             panic!("Subscription already cancelled");
         }
 
-        let mut cancelled_sub = subscription;
-        cancelled_sub.is_active = false;
-        env.storage().instance().set(&DataKey::Subscription(subscription_id), &cancelled_sub);
+        subscription.is_active = false;
+        // Cancelled subscriptions are no longer touched, so their storage is
+        // left to expire and be archived instead of being extended further.
+        env.storage()
+            .persistent()
+            .set(&DataKey::Subscription(subscription_id), &subscription);
+
+        env.events().publish(
+            (symbol_short!("sub"), symbol_short!("cancelled"), subscription_id),
+            subscription.owner,
+        );
     }
 
     /// Renew a subscription (can be called by anyone when due)
     pub fn renew_subscription(env: Env, subscription_id: u64) -> bool {
-        let subscription: Subscription = env
-            .storage()
-            .instance()
-            .get(&DataKey::Subscription(subscription_id))
-            .unwrap_or_else(|| panic!("Subscription not found"));
+        let subscription = Self::load_subscription(&env, subscription_id);
 
         if !subscription.is_active {
             panic!("Subscription is not active");
         }
 
-        let current_time = env.ledger().timestamp() as u64;
-        
+        let current_time = env.ledger().timestamp();
+
         if current_time < subscription.next_billing_date {
             return false; // Not yet due
         }
 
-        // Transfer payment from subscriber to contract (in a real implementation, this would use token transfers)
-        // For this example, we'll just update balances and dates
-        
-        // Calculate split amounts
-        let split_amount = (subscription.amount as u128)
-            .checked_mul(subscription.split_percentage as u128)
-            .and_then(|x| x.checked_div(MAX_SPLIT_PERCENTAGE as u128))
-            .unwrap_or(0) as i128;
-
-        // Update recipient balance
-        let recipient_balance: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::RecipientBalance(subscription.recipient.clone()))
-            .unwrap_or(0);
-        let new_balance = recipient_balance.checked_add(split_amount).unwrap();
-        env.storage().instance().set(
-            &DataKey::RecipientBalance(subscription.recipient.clone()),
-            &new_balance,
+        // Pull the full payment from the subscriber into the contract using the
+        // allowance granted at `create_subscription` time, so any keeper can
+        // trigger this without the subscriber signing the renewal itself.
+        let token_client = token::Client::new(&env, &subscription.token);
+        token_client.transfer_from(
+            &env.current_contract_address(),
+            &subscription.subscriber,
+            &env.current_contract_address(),
+            &subscription.amount,
         );
 
+        if subscription.escrow {
+            // Hold the payment until the owner releases it (or it's refunded past the
+            // deadline). The deadline is re-armed on every renewal rather than fixed
+            // at creation time, so it always tracks the most recently collected cycle.
+            let mut escrow_state = Self::load_escrow(&env, subscription_id);
+            escrow_state.held = escrow_state.held.checked_add(subscription.amount).unwrap();
+            escrow_state.refund_deadline = current_time
+                .checked_add(subscription.escrow_refund_after_seconds)
+                .unwrap();
+            Self::save_escrow(&env, subscription_id, escrow_state);
+        } else {
+            Self::distribute_amount(&env, &subscription, subscription.amount);
+        }
+
         // Update subscription dates
         let mut renewed_sub = subscription;
         renewed_sub.last_payment_date = current_time;
@@ -189,46 +308,166 @@ impl SubSor {
         renewed_sub.next_billing_date = current_time
             .checked_add(period_seconds)
             .unwrap();
-        
-        env.storage().instance().set(&DataKey::Subscription(subscription_id), &renewed_sub);
+
+        Self::save_subscription(&env, &renewed_sub);
+
+        env.events().publish(
+            (symbol_short!("sub"), symbol_short!("renewed"), subscription_id),
+            (renewed_sub.amount, renewed_sub.splits.clone(), renewed_sub.next_billing_date),
+        );
 
         true
     }
 
     /// Get subscription details
     pub fn get_subscription(env: Env, subscription_id: u64) -> Subscription {
-        env.storage()
-            .instance()
-            .get(&DataKey::Subscription(subscription_id))
-            .unwrap_or_else(|| panic!("Subscription not found"))
+        Self::load_subscription(&env, subscription_id)
+    }
+
+    /// Pay to extend a subscription's storage lifetime. Callable by anyone,
+    /// since keeping a subscription alive benefits subscriber and recipients alike.
+    pub fn bump_subscription(env: Env, subscription_id: u64) {
+        let subscription = Self::load_subscription(&env, subscription_id);
+        if !subscription.is_active {
+            panic!("Cannot bump a cancelled subscription");
+        }
+        Self::save_subscription(&env, &subscription);
+    }
+
+    /// Distribute a subscription's held escrow across its splits. Callable by the owner.
+    pub fn release_escrow(env: Env, subscription_id: u64) {
+        let subscription = Self::load_subscription(&env, subscription_id);
+        subscription.owner.require_auth();
+
+        if !subscription.escrow {
+            panic!("Subscription does not use escrow");
+        }
+
+        let escrow_state = Self::load_escrow(&env, subscription_id);
+        if escrow_state.held <= 0 {
+            panic!("No escrow funds to release");
+        }
+        Self::save_escrow(&env, subscription_id, Escrow { held: 0, refund_deadline: 0 });
+
+        Self::distribute_amount(&env, &subscription, escrow_state.held);
+
+        env.events().publish(
+            (symbol_short!("sub"), symbol_short!("released"), subscription_id),
+            escrow_state.held,
+        );
+    }
+
+    /// Return a subscription's held escrow to the subscriber, once the refund deadline
+    /// for that held cycle has passed. Callable only by the subscriber, since it's
+    /// their own funds being reclaimed.
+    pub fn refund_escrow(env: Env, subscription_id: u64) {
+        let subscription = Self::load_subscription(&env, subscription_id);
+        subscription.subscriber.require_auth();
+
+        if !subscription.escrow {
+            panic!("Subscription does not use escrow");
+        }
+
+        let escrow_state = Self::load_escrow(&env, subscription_id);
+        if env.ledger().timestamp() < escrow_state.refund_deadline {
+            panic!("Escrow deadline has not passed");
+        }
+        if escrow_state.held <= 0 {
+            panic!("No escrow funds to refund");
+        }
+        Self::save_escrow(&env, subscription_id, Escrow { held: 0, refund_deadline: 0 });
+
+        let token_client = token::Client::new(&env, &subscription.token);
+        token_client.transfer(
+            &env.current_contract_address(),
+            &subscription.subscriber,
+            &escrow_state.held,
+        );
+
+        env.events().publish(
+            (symbol_short!("sub"), symbol_short!("refunded"), subscription_id),
+            escrow_state.held,
+        );
     }
 
-    /// Withdraw accumulated revenue for a recipient
+    /// Withdraw accumulated revenue for a recipient: their instantly-withdrawable
+    /// balance plus whatever has vested and not yet been claimed across all schedules
     pub fn withdraw_revenue(env: Env, recipient: Address) -> i128 {
         recipient.require_auth();
-        
-        let balance: i128 = env
-            .storage()
-            .instance()
-            .get(&DataKey::RecipientBalance(recipient.clone()))
-            .unwrap_or(0);
 
+        let instant_balance = Self::load_recipient_balance(&env, &recipient);
+
+        let now = env.ledger().timestamp();
+        let schedules = Self::load_vesting_schedules(&env, &recipient);
+        let mut updated_schedules = Vec::new(&env);
+        let mut vested_claim = 0i128;
+        for mut schedule in schedules.iter() {
+            let claimable = Self::claimable_amount(now, &schedule);
+            let claim = claimable.checked_sub(schedule.withdrawn).unwrap();
+            if claim > 0 {
+                schedule.withdrawn = claimable;
+                vested_claim = vested_claim.checked_add(claim).unwrap();
+            }
+            // Drop schedules that are fully vested and fully withdrawn instead of
+            // carrying them forward forever, or this vector grows unbounded.
+            if schedule.withdrawn >= schedule.total {
+                continue;
+            }
+            updated_schedules.push_back(schedule);
+        }
+
+        let balance = instant_balance.checked_add(vested_claim).unwrap();
         if balance <= 0 {
             return 0;
         }
 
-        // Reset balance (in real implementation, would transfer tokens here)
-        env.storage().instance().set(&DataKey::RecipientBalance(recipient), &0i128);
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .unwrap_or_else(|| panic!("Contract not initialized"));
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        let token_client = token::Client::new(&env, &token);
+        let contract_balance = token_client.balance(&env.current_contract_address());
+        if contract_balance < balance {
+            panic!("Insufficient contract token balance");
+        }
+
+        Self::save_recipient_balance(&env, &recipient, 0);
+        if vested_claim > 0 || updated_schedules.len() != schedules.len() {
+            Self::save_vesting_schedules(&env, &recipient, &updated_schedules);
+        }
+
+        token_client.transfer(&env.current_contract_address(), &recipient, &balance);
+
+        env.events().publish(
+            (symbol_short!("sub"), symbol_short!("withdrawn"), recipient),
+            balance,
+        );
 
         balance
     }
 
-    /// Get balance for a recipient
+    /// Get the instantly-withdrawable balance for a recipient (excludes vesting schedules)
     pub fn get_balance(env: Env, recipient: Address) -> i128 {
-        env.storage()
-            .instance()
-            .get(&DataKey::RecipientBalance(recipient))
-            .unwrap_or(0)
+        Self::load_recipient_balance(&env, &recipient)
+    }
+
+    /// Get the amount currently claimable across a recipient's vesting schedules,
+    /// i.e. already unlocked but not yet withdrawn. Distinct from the still-locked total.
+    pub fn get_vested_balance(env: Env, recipient: Address) -> i128 {
+        let now = env.ledger().timestamp();
+        let schedules = Self::load_vesting_schedules(&env, &recipient);
+        let mut claimable_total = 0i128;
+        for schedule in schedules.iter() {
+            let claimable = Self::claimable_amount(now, &schedule);
+            claimable_total = claimable_total
+                .checked_add(claimable.checked_sub(schedule.withdrawn).unwrap())
+                .unwrap();
+        }
+        claimable_total
     }
 
     /// List subscriptions for an owner with pagination
@@ -238,11 +477,7 @@ impl SubSor {
         start_after: Option<u64>,
         limit: u32,
     ) -> Vec<Subscription> {
-        let subscription_ids: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&DataKey::OwnerSubscriptions(owner))
-            .unwrap_or(Vec::new(&env));
+        let subscription_ids = Self::load_owner_subscriptions(&env, &owner);
 
         let mut result = Vec::new(&env);
         let mut found_start = start_after.is_none();
@@ -260,7 +495,11 @@ impl SubSor {
                 break;
             }
 
-            if let Some(sub) = env.storage().instance().get::<DataKey, Subscription>(&DataKey::Subscription(id)) {
+            if let Some(sub) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, Subscription>(&DataKey::Subscription(id))
+            {
                 result.push_back(sub);
                 count += 1;
             }
@@ -271,16 +510,16 @@ impl SubSor {
 
     /// Get all subscriptions for an owner
     pub fn get_all_subscriptions(env: Env, owner: Address) -> Vec<Subscription> {
-        let subscription_ids: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&DataKey::OwnerSubscriptions(owner))
-            .unwrap_or(Vec::new(&env));
+        let subscription_ids = Self::load_owner_subscriptions(&env, &owner);
 
         let mut result = Vec::new(&env);
 
         for id in subscription_ids.iter() {
-            if let Some(sub) = env.storage().instance().get::<DataKey, Subscription>(&DataKey::Subscription(id)) {
+            if let Some(sub) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, Subscription>(&DataKey::Subscription(id))
+            {
                 result.push_back(sub);
             }
         }
@@ -290,21 +529,21 @@ impl SubSor {
 
     /// Check and auto-renew all due subscriptions (helper function)
     pub fn process_due_subscriptions(env: Env, owner: Address, max_count: u32) -> u32 {
-        let subscription_ids: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&DataKey::OwnerSubscriptions(owner))
-            .unwrap_or(Vec::new(&env));
+        let subscription_ids = Self::load_owner_subscriptions(&env, &owner);
 
         let mut renewed = 0u32;
-        let current_time = env.ledger().timestamp() as u64;
+        let current_time = env.ledger().timestamp();
 
         for id in subscription_ids.iter() {
             if renewed >= max_count {
                 break;
             }
 
-            if let Some(sub) = env.storage().instance().get::<DataKey, Subscription>(&DataKey::Subscription(id)) {
+            if let Some(sub) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, Subscription>(&DataKey::Subscription(id))
+            {
                 if sub.is_active && current_time >= sub.next_billing_date {
                     // Renew this subscription
                     let _ = Self::renew_subscription(env.clone(), id);
@@ -315,6 +554,203 @@ impl SubSor {
 
         renewed
     }
+
+    /// Credit `amount` across a subscription's splits, vesting each cut if enabled,
+    /// with whatever the splits don't claim (unassigned basis points and rounding
+    /// remainders) going to the owner unvested
+    fn distribute_amount(env: &Env, subscription: &Subscription, amount: i128) {
+        let mut remaining = amount;
+
+        for split in subscription.splits.iter() {
+            let split_amount = (amount as u128)
+                .checked_mul(split.basis_points as u128)
+                .and_then(|x| x.checked_div(MAX_SPLIT_BASIS_POINTS as u128))
+                .unwrap_or(0) as i128;
+            remaining = remaining.checked_sub(split_amount).unwrap();
+
+            if subscription.vesting_enabled {
+                let mut schedules = Self::load_vesting_schedules(env, &split.recipient);
+                schedules.push_back(VestingSchedule {
+                    start_ledger_time: env.ledger().timestamp(),
+                    duration_seconds: subscription.vesting_duration_seconds,
+                    total: split_amount,
+                    withdrawn: 0,
+                    cliff_seconds: subscription.vesting_cliff_seconds,
+                });
+                Self::save_vesting_schedules(env, &split.recipient, &schedules);
+            } else {
+                let balance = Self::load_recipient_balance(env, &split.recipient);
+                Self::save_recipient_balance(
+                    env,
+                    &split.recipient,
+                    balance.checked_add(split_amount).unwrap(),
+                );
+            }
+        }
+
+        // Whatever the splits didn't claim goes to the owner, unvested
+        let owner_balance = Self::load_recipient_balance(env, &subscription.owner);
+        Self::save_recipient_balance(
+            env,
+            &subscription.owner,
+            owner_balance.checked_add(remaining).unwrap(),
+        );
+    }
+
+    /// Read a subscription from persistent storage, extending its TTL if still active
+    fn load_subscription(env: &Env, subscription_id: u64) -> Subscription {
+        let key = DataKey::Subscription(subscription_id);
+        let subscription: Subscription = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic!("Subscription not found"));
+
+        if subscription.is_active {
+            let rent_ledgers = Self::rent_ledgers(env);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, rent_ledgers.saturating_sub(DAY_IN_LEDGERS), rent_ledgers);
+        }
+
+        subscription
+    }
+
+    /// Write a subscription to persistent storage, extending its TTL and
+    /// refreshing `last_extended_ledger` since it is still active
+    fn save_subscription(env: &Env, subscription: &Subscription) {
+        let key = DataKey::Subscription(subscription.id);
+        let mut subscription = subscription.clone();
+        subscription.last_extended_ledger = env.ledger().sequence();
+        env.storage().persistent().set(&key, &subscription);
+
+        let rent_ledgers = Self::rent_ledgers(env);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, rent_ledgers.saturating_sub(DAY_IN_LEDGERS), rent_ledgers);
+    }
+
+    /// Read a recipient's accrued balance from persistent storage, extending its TTL
+    /// only if the key already exists (a never-set key can't have its TTL extended)
+    fn load_recipient_balance(env: &Env, recipient: &Address) -> i128 {
+        let key = DataKey::RecipientBalance(recipient.clone());
+        let balance: Option<i128> = env.storage().persistent().get(&key);
+        if balance.is_some() {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        }
+        balance.unwrap_or(0)
+    }
+
+    /// Write a recipient's accrued balance to persistent storage, extending its TTL
+    fn save_recipient_balance(env: &Env, recipient: &Address, balance: i128) {
+        let key = DataKey::RecipientBalance(recipient.clone());
+        env.storage().persistent().set(&key, &balance);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+
+    /// Read an owner's list of subscription ids from persistent storage, extending its TTL
+    /// only if the key already exists (a never-set key can't have its TTL extended)
+    fn load_owner_subscriptions(env: &Env, owner: &Address) -> Vec<u64> {
+        let key = DataKey::OwnerSubscriptions(owner.clone());
+        let subs: Option<Vec<u64>> = env.storage().persistent().get(&key);
+        if subs.is_some() {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        }
+        subs.unwrap_or(Vec::new(env))
+    }
+
+    /// Write an owner's list of subscription ids to persistent storage, extending its TTL
+    fn save_owner_subscriptions(env: &Env, owner: &Address, subs: &Vec<u64>) {
+        let key = DataKey::OwnerSubscriptions(owner.clone());
+        env.storage().persistent().set(&key, subs);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+
+    /// Read a recipient's vesting schedules from persistent storage, extending their TTL
+    /// only if the key already exists (a never-set key can't have its TTL extended)
+    fn load_vesting_schedules(env: &Env, recipient: &Address) -> Vec<VestingSchedule> {
+        let key = DataKey::VestingSchedules(recipient.clone());
+        let schedules: Option<Vec<VestingSchedule>> = env.storage().persistent().get(&key);
+        if schedules.is_some() {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        }
+        schedules.unwrap_or(Vec::new(env))
+    }
+
+    /// Write a recipient's vesting schedules to persistent storage, extending their TTL
+    fn save_vesting_schedules(env: &Env, recipient: &Address, schedules: &Vec<VestingSchedule>) {
+        let key = DataKey::VestingSchedules(recipient.clone());
+        env.storage().persistent().set(&key, schedules);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+
+    /// Amount unlocked by `now` under a single vesting schedule, ignoring what was
+    /// already withdrawn. Zero before the cliff, linear after, capped at `total`.
+    fn claimable_amount(now: u64, schedule: &VestingSchedule) -> i128 {
+        let cliff_end = schedule.start_ledger_time.checked_add(schedule.cliff_seconds).unwrap();
+        if now < cliff_end {
+            return 0;
+        }
+
+        let elapsed = now.checked_sub(schedule.start_ledger_time).unwrap();
+        if elapsed >= schedule.duration_seconds {
+            return schedule.total;
+        }
+
+        schedule
+            .total
+            .checked_mul(elapsed as i128)
+            .unwrap()
+            .checked_div(schedule.duration_seconds as i128)
+            .unwrap()
+    }
+
+    /// Read a subscription's held escrow state from persistent storage, extending its TTL
+    /// only if the key already exists (a never-set key can't have its TTL extended)
+    fn load_escrow(env: &Env, subscription_id: u64) -> Escrow {
+        let key = DataKey::Escrow(subscription_id);
+        let escrow_state: Option<Escrow> = env.storage().persistent().get(&key);
+        if escrow_state.is_some() {
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        }
+        escrow_state.unwrap_or(Escrow { held: 0, refund_deadline: 0 })
+    }
+
+    /// Write a subscription's held escrow state to persistent storage, extending its TTL
+    fn save_escrow(env: &Env, subscription_id: u64, escrow_state: Escrow) {
+        let key = DataKey::Escrow(subscription_id);
+        env.storage().persistent().set(&key, &escrow_state);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+
+    /// Read the configured rent-ledgers window, extending the contract's own instance
+    /// TTL in the process. This is the instance-storage entry point hit by nearly every
+    /// call, so it keeps Token/RentLedgers/SubscriptionCounter/Initialized from archiving.
+    fn rent_ledgers(env: &Env) -> u32 {
+        env.storage()
+            .instance()
+            .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+        env.storage()
+            .instance()
+            .get(&DataKey::RentLedgers)
+            .unwrap_or(DEFAULT_RENT_LEDGERS)
+    }
 }
 
 #[cfg(test)]